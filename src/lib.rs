@@ -1,4 +1,6 @@
-#![cfg_attr(feature = "nightly", feature(specialization))]
+#![cfg_attr(feature = "nightly", feature(min_specialization, rustc_attrs))]
+#![cfg_attr(feature = "nightly", allow(internal_features))]
+#![cfg_attr(not(feature = "std"), no_std)]
 
 //! This crate provides Go style copying / cloning for slices.
 //!
@@ -6,6 +8,9 @@
 //! elements copied, as opposed to determining the amount to copy before adjusting slices and
 //! finally copying.
 //!
+//! The `std` feature is enabled by default; disable default features to use this crate in a
+//! `no_std` environment.
+//!
 //! # Examples
 //!
 //! We can use `copy` for types that implement `Copy`.
@@ -36,7 +41,15 @@
 //! assert_eq!(l, b"bizbarbaz");
 //! ```
 
-use std::cmp::min;
+use core::cmp::min;
+
+#[cfg(test)]
+extern crate alloc;
+
+#[cfg(test)]
+use alloc::vec::Vec;
+#[cfg(test)]
+use alloc::vec;
 
 #[cfg(feature = "nightly")]
 trait Cpy<T = Self>
@@ -47,12 +60,12 @@ where
 }
 
 #[cfg(feature = "nightly")]
-default impl<T> Cpy<[T]> for [T]
+impl<T> Cpy<[T]> for [T]
 where
     T: Copy,
 {
     #[inline]
-    fn copy(&mut self, src: &Self) -> usize {
+    default fn copy(&mut self, src: &Self) -> usize {
         let len = min(src.len(), self.len());
         (&mut self[..len]).copy_from_slice(&src[..len]);
         len
@@ -63,11 +76,43 @@ where
 impl Cpy<[u8]> for [u8] {
     #[inline]
     fn copy(&mut self, src: &Self) -> usize {
-        use std::io::Read;
         let len = min(src.len(), self.len());
-        (&src[..len])
-            .read(&mut self[..len])
-            .expect("&[u8] reads never error")
+        copy_bytes(self, src, len);
+        len
+    }
+}
+
+/// Copies `len` bytes from `src` into `dst` without calling into `memcpy` for small
+/// lengths, where the fixed call overhead dominates.
+///
+/// A `len` of 0 copies nothing and a `len` of 1 is a single byte assignment. For
+/// `2 <= len <= 32`, this performs two overlapping fixed-size loads/stores (each a single
+/// integer of the largest power-of-two size up to 16 bytes that fits) rather than a byte-wise
+/// or `memcpy` copy; the overlap in the middle is harmless since both copies write the same
+/// bytes. Lengths above 32 fall back to `copy_from_slice`.
+#[cfg(feature = "nightly")]
+#[inline]
+fn copy_bytes(dst: &mut [u8], src: &[u8], len: usize) {
+    macro_rules! block_copy {
+        ($ty:ty) => {{
+            const B: usize = core::mem::size_of::<$ty>();
+            unsafe {
+                let front = (src.as_ptr() as *const $ty).read_unaligned();
+                let back = (src.as_ptr().add(len - B) as *const $ty).read_unaligned();
+                (dst.as_mut_ptr() as *mut $ty).write_unaligned(front);
+                (dst.as_mut_ptr().add(len - B) as *mut $ty).write_unaligned(back);
+            }
+        }};
+    }
+
+    match len {
+        0 => {}
+        1 => dst[0] = src[0],
+        2..=3 => block_copy!(u16),
+        4..=7 => block_copy!(u32),
+        8..=15 => block_copy!(u64),
+        16..=32 => block_copy!(u128),
+        _ => dst[..len].copy_from_slice(&src[..len]),
     }
 }
 
@@ -75,10 +120,8 @@ impl Cpy<[u8]> for [u8] {
 /// function is short form for `dst.copy_from_slice(src)`, but accounts for if their lengths are
 /// unequal to avoid panics.
 ///
-/// With the `nightly` feature, `[u8]` is specialized to use [`Read`], which is implemented
-/// specially for small slices.
-///
-/// [`Read`]: https://doc.rust-lang.org/std/primitive.slice.html#impl-Read
+/// With the `nightly` feature, `[u8]` is specialized to avoid `memcpy` for lengths up to 32
+/// bytes.
 ///
 /// # Examples
 ///
@@ -110,10 +153,46 @@ where
     }
 }
 
+/// Copies as many `T` as possible from `src` into `dst`, like [`copy`], but also returns the
+/// unconsumed tails of `dst` and `src`. This lets a caller building a scatter/gather-style
+/// serialization loop thread a cursor through multiple copies, Go's `n := copy(dst, src); dst =
+/// dst[n:]` idiom, without recomputing offsets or risking off-by-one slicing panics.
+///
+/// # Examples
+///
+/// ```
+/// use slice_copy::copy_advance;
+///
+/// let mut buf = [0u8; 5];
+/// let mut dst = &mut buf[..];
+///
+/// let (n, rest, _) = copy_advance(dst, b"hi");
+/// assert_eq!(n, 2);
+/// dst = rest;
+///
+/// let (n, rest, _) = copy_advance(dst, b"there");
+/// assert_eq!(n, 3);
+/// dst = rest;
+///
+/// assert!(dst.is_empty());
+/// assert_eq!(buf, *b"hithe");
+/// ```
+#[inline]
+pub fn copy_advance<'d, 's, T>(dst: &'d mut [T], src: &'s [T]) -> (usize, &'d mut [T], &'s [T])
+where
+    T: Copy,
+{
+    let n = copy(dst, src);
+    (n, &mut dst[n..], &src[n..])
+}
+
 /// Clones as many `T` as possible from `src` into `dst`, returning the number of `T` cloned. This
 /// function is short form for `dst.clone_from_slice(src)`, but accounts for if their lengths are
 /// unequal to avoid panics.
 ///
+/// With the `nightly` feature, `T: Copy` is specialized to use a single bitwise copy (`memcpy`)
+/// rather than a per-element clone loop.
+///
 /// Examples
 ///
 /// ```
@@ -133,7 +212,136 @@ where
     T: Clone,
 {
     let len = min(src.len(), dst.len());
-    (&mut dst[..len]).clone_from_slice(&src[..len]);
+    (&mut dst[..len]).spec_clone_from(&src[..len]);
+    len
+}
+
+/// Clones as many `T` as possible from `src` into `dst`, like [`clone`], but also returns the
+/// unconsumed tails of `dst` and `src`. See [`copy_advance`] for why this is useful.
+///
+/// # Examples
+///
+/// ```
+/// use slice_copy::clone_advance;
+///
+/// let mut buf = vec![0u8; 5];
+/// let mut dst = &mut buf[..];
+///
+/// let (n, rest, _) = clone_advance(dst, &b"hi"[..]);
+/// assert_eq!(n, 2);
+/// dst = rest;
+///
+/// let (n, rest, _) = clone_advance(dst, &b"there"[..]);
+/// assert_eq!(n, 3);
+/// dst = rest;
+///
+/// assert!(dst.is_empty());
+/// assert_eq!(buf, b"hithe");
+/// ```
+#[inline]
+pub fn clone_advance<'d, 's, T>(dst: &'d mut [T], src: &'s [T]) -> (usize, &'d mut [T], &'s [T])
+where
+    T: Clone,
+{
+    let n = clone(dst, src);
+    (n, &mut dst[n..], &src[n..])
+}
+
+trait SpecCloneFrom<T> {
+    fn spec_clone_from(&mut self, src: &[T]);
+}
+
+#[cfg(feature = "nightly")]
+impl<T> SpecCloneFrom<T> for [T]
+where
+    T: Clone,
+{
+    #[inline]
+    default fn spec_clone_from(&mut self, src: &[T]) {
+        self.clone_from_slice(src);
+    }
+}
+
+/// Marker trait for `min_specialization`: every `Copy` type implements it, which lets the
+/// `Copy`-specialized impl below be recognized as strictly more specific than its
+/// `Clone`-bounded default impl, as `min_specialization` otherwise can't order two blanket
+/// impls that differ only by trait bound. `rustc` refuses to let an impl specialize directly
+/// on `Copy`/`Clone`, which is why this goes through the marker and a raw `memcpy` instead of
+/// a `T: Copy` bound and `copy_from_slice`.
+#[cfg(feature = "nightly")]
+#[rustc_unsafe_specialization_marker]
+trait SpecMarkerCopy: Clone {}
+
+#[cfg(feature = "nightly")]
+impl<T: Copy> SpecMarkerCopy for T {}
+
+#[cfg(feature = "nightly")]
+impl<T> SpecCloneFrom<T> for [T]
+where
+    T: SpecMarkerCopy,
+{
+    #[inline]
+    fn spec_clone_from(&mut self, src: &[T]) {
+        // SAFETY: `SpecMarkerCopy` is only implemented for `T: Copy`, so a bitwise copy is a
+        // valid clone; `self` and `src` are the same length, as guaranteed by `clone`.
+        unsafe {
+            core::ptr::copy_nonoverlapping(src.as_ptr(), self.as_mut_ptr(), self.len());
+        }
+    }
+}
+
+#[cfg(not(feature = "nightly"))]
+impl<T> SpecCloneFrom<T> for [T]
+where
+    T: Clone,
+{
+    #[inline]
+    fn spec_clone_from(&mut self, src: &[T]) {
+        self.clone_from_slice(src);
+    }
+}
+
+/// Copies as many `T` as fit from `buf[src]` to the region of `buf` starting at `dest_start`,
+/// returning the number of `T` moved. Both the source range and the destination region are
+/// clamped to `buf`'s bounds, so this never panics. This is the same operation as Go's `copy`
+/// when source and destination are subslices of the same slice, which the two-slice `copy` and
+/// `clone` functions can't express because they can't borrow the same slice mutably and
+/// immutably at once.
+///
+/// Source and destination are allowed to overlap; the copy direction is chosen so that
+/// overlapping regions aren't corrupted.
+///
+/// # Examples
+///
+/// ```
+/// use slice_copy::copy_within;
+///
+/// let mut v = b"hello world".to_vec();
+///
+/// let n = copy_within(&mut v, 6..11, 0);
+///
+/// assert_eq!(n, 5);
+/// assert_eq!(v, b"world world");
+/// ```
+#[inline]
+pub fn copy_within<T>(buf: &mut [T], src: core::ops::Range<usize>, dest_start: usize) -> usize
+where
+    T: Copy,
+{
+    let src_start = min(src.start, buf.len());
+    let src_end = min(src.end, buf.len()).max(src_start);
+    let len = min(src_end - src_start, buf.len().saturating_sub(dest_start));
+
+    if dest_start > src_start {
+        for i in (0..len).rev() {
+            buf[dest_start + i] = buf[src_start + i];
+        }
+    } else {
+        for i in 0..len {
+            buf[dest_start + i] = buf[src_start + i];
+        }
+    }
+
     len
 }
 
@@ -166,3 +374,81 @@ fn test_copy() {
     assert_eq!(l, b"hello");
     assert_eq!(r, b"goodbye");
 }
+
+#[cfg(feature = "nightly")]
+#[test]
+fn test_copy_bytes_boundaries() {
+    for &len in &[0usize, 1, 2, 3, 4, 7, 8, 15, 16, 17, 32, 33] {
+        let src: Vec<u8> = (0..len as u8).collect();
+
+        let mut via_copy_bytes = vec![0xffu8; len];
+        copy_bytes(&mut via_copy_bytes, &src, len);
+        assert_eq!(via_copy_bytes, src, "copy_bytes len = {}", len);
+
+        let mut via_copy = vec![0xffu8; len];
+        assert_eq!(copy(&mut via_copy, &src), len, "copy len = {}", len);
+        assert_eq!(via_copy, src, "copy len = {}", len);
+    }
+}
+
+#[test]
+fn test_copy_within() {
+    // forward (dest before src), no overlap
+    let mut v = b"hello world".to_vec();
+    assert_eq!(copy_within(&mut v, 6..11, 0), 5);
+    assert_eq!(v, b"world world");
+
+    // backward (dest after src), no overlap
+    let mut v = b"hello world".to_vec();
+    assert_eq!(copy_within(&mut v, 0..5, 6), 5);
+    assert_eq!(v, b"hello hello");
+
+    // forward, overlapping
+    let mut v = b"abcdefgh".to_vec();
+    assert_eq!(copy_within(&mut v, 2..8, 0), 6);
+    assert_eq!(v, b"cdefghgh");
+
+    // src range clamped to buf bounds
+    let mut v = b"abcde".to_vec();
+    assert_eq!(copy_within(&mut v, 3..100, 0), 2);
+    assert_eq!(v, b"decde");
+
+    // dest_start beyond buf length moves nothing
+    let mut v = b"abcde".to_vec();
+    assert_eq!(copy_within(&mut v, 0..5, 10), 0);
+    assert_eq!(v, b"abcde");
+}
+
+#[test]
+fn test_copy_advance() {
+    let mut buf = [0u8; 5];
+    let dst = &mut buf[..];
+
+    let (n, dst, src) = copy_advance(dst, b"hi");
+    assert_eq!(n, 2);
+    assert_eq!(src, b"");
+
+    let (n, dst, src) = copy_advance(dst, b"there");
+    assert_eq!(n, 3);
+    assert_eq!(src, b"re");
+    assert!(dst.is_empty());
+
+    assert_eq!(buf, *b"hithe");
+}
+
+#[test]
+fn test_clone_advance() {
+    let mut buf = vec![0u8; 5];
+    let dst = &mut buf[..];
+
+    let (n, dst, src) = clone_advance(dst, &b"hi"[..]);
+    assert_eq!(n, 2);
+    assert_eq!(src, b"");
+
+    let (n, dst, src) = clone_advance(dst, &b"there"[..]);
+    assert_eq!(n, 3);
+    assert_eq!(src, b"re");
+    assert!(dst.is_empty());
+
+    assert_eq!(buf, b"hithe");
+}